@@ -0,0 +1,262 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::write_atomic;
+
+/// The newest this many snapshots are always kept, regardless of tiering
+const KEEP_RECENT: usize = 10;
+/// Beyond the recent window, keep at most one snapshot per day for this many days
+const KEEP_DAILY_DAYS: i64 = 14;
+/// Beyond the daily window, keep at most one snapshot per week for this many days
+const KEEP_WEEKLY_DAYS: i64 = 90;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupInfo {
+    pub timestamp: String,
+    pub source_filename: String,
+    pub size: u64,
+    pub path: String,
+}
+
+fn backup_cache() -> &'static Mutex<HashMap<String, Vec<BackupInfo>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Vec<BackupInfo>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn invalidate_cache(filename: &str) {
+    if let Ok(mut cache) = backup_cache().lock() {
+        cache.remove(filename);
+    }
+}
+
+/// Directory holding every backup for a single ledger, next to the resolved ledgers directory
+fn backups_dir_for(filename: &str) -> Result<PathBuf, String> {
+    let ledgers_dir = crate::get_ledgers_dir()?;
+    let backups_root = ledgers_dir
+        .parent()
+        .map(|parent| parent.join("backups"))
+        .unwrap_or_else(|| ledgers_dir.join("backups"));
+    let dir = backups_root.join(filename);
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create backups directory: {}", e))?;
+    }
+
+    Ok(dir)
+}
+
+fn read_backup_info(path: &Path, filename: &str) -> Option<BackupInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    let timestamp = path.file_stem()?.to_str()?.to_string();
+
+    Some(BackupInfo {
+        timestamp,
+        source_filename: filename.to_string(),
+        size: metadata.len(),
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// List every backup snapshot for `filename`, newest first, with per-filename caching
+pub fn list_backups(filename: &str) -> Result<Vec<BackupInfo>, String> {
+    if let Ok(cache) = backup_cache().lock() {
+        if let Some(cached) = cache.get(filename) {
+            return Ok(cached.clone());
+        }
+    }
+
+    let dir = backups_dir_for(filename)?;
+    let mut backups = Vec::new();
+
+    for entry in fs::read_dir(&dir).map_err(|e| format!("Failed to read backups directory: {}", e))? {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            if let Some(info) = read_backup_info(&path, filename) {
+                backups.push(info);
+            }
+        }
+    }
+
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    if let Ok(mut cache) = backup_cache().lock() {
+        cache.insert(filename.to_string(), backups.clone());
+    }
+
+    Ok(backups)
+}
+
+/// Snapshot `content` for `filename` into the backups directory, then prune to the rolling window
+pub fn snapshot(filename: &str, content: &str) -> Result<(), String> {
+    let dir = backups_dir_for(filename)?;
+    let timestamp = Utc::now().to_rfc3339();
+    let snapshot_path = dir.join(format!("{}.json", timestamp));
+
+    write_atomic(&snapshot_path, content)?;
+    invalidate_cache(filename);
+    prune(filename)?;
+
+    Ok(())
+}
+
+/// Decide which of `backups` (already sorted newest-first) the retention window prunes
+fn timestamps_to_prune(now: DateTime<Utc>, backups: &[BackupInfo]) -> Vec<String> {
+    let mut kept_days = HashSet::new();
+    let mut kept_weeks = HashSet::new();
+    let mut to_prune = Vec::new();
+
+    for (i, backup) in backups.iter().enumerate() {
+        if i < KEEP_RECENT {
+            continue;
+        }
+
+        let parsed = DateTime::parse_from_rfc3339(&backup.timestamp).ok();
+        let age_days = parsed
+            .map(|t| (now - t.with_timezone(&Utc)).num_days())
+            .unwrap_or(0);
+
+        let keep = if age_days <= KEEP_DAILY_DAYS {
+            let day_key = parsed.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default();
+            kept_days.insert(day_key)
+        } else if age_days <= KEEP_WEEKLY_DAYS {
+            let week_key = parsed
+                .map(|t| format!("{}-W{}", t.format("%G"), t.format("%V")))
+                .unwrap_or_default();
+            kept_weeks.insert(week_key)
+        } else {
+            false
+        };
+
+        if !keep {
+            to_prune.push(backup.timestamp.clone());
+        }
+    }
+
+    to_prune
+}
+
+/// Apply the rolling retention window, deleting whatever `timestamps_to_prune` selects
+fn prune(filename: &str) -> Result<(), String> {
+    let dir = backups_dir_for(filename)?;
+    let mut backups = list_backups(filename)?;
+    backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    if backups.len() <= KEEP_RECENT {
+        return Ok(());
+    }
+
+    for timestamp in timestamps_to_prune(Utc::now(), &backups) {
+        let _ = fs::remove_file(dir.join(format!("{}.json", timestamp)));
+    }
+
+    invalidate_cache(filename);
+    Ok(())
+}
+
+/// Atomically restore `backup_path` over `ledger_path`, snapshotting the pre-restore state first
+pub fn restore(ledger_path: &Path, backup_path: &Path) -> Result<(), String> {
+    let backup_content = fs::read_to_string(backup_path)
+        .map_err(|e| format!("Failed to read backup: {}", e))?;
+
+    let filename = ledger_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "Invalid ledger path".to_string())?
+        .to_string();
+
+    if let Ok(pre_restore_content) = fs::read_to_string(ledger_path) {
+        snapshot(&filename, &pre_restore_content)?;
+    }
+
+    write_atomic(ledger_path, &backup_content)?;
+    crate::write_meta(ledger_path, &backup_content)?;
+
+    Ok(())
+}
+
+/// Delete a single backup snapshot
+pub fn delete(backup_path: &Path, filename: &str) -> Result<(), String> {
+    fs::remove_file(backup_path).map_err(|e| format!("Failed to delete backup: {}", e))?;
+    invalidate_cache(filename);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn backup_at(now: DateTime<Utc>, age_days: i64) -> BackupInfo {
+        BackupInfo {
+            timestamp: (now - Duration::days(age_days)).to_rfc3339(),
+            source_filename: "ledger.json".to_string(),
+            size: 0,
+            path: String::new(),
+        }
+    }
+
+    #[test]
+    fn keeps_everything_within_recent_window() {
+        let now = Utc::now();
+        let backups: Vec<BackupInfo> = (0..KEEP_RECENT as i64).map(|i| backup_at(now, i)).collect();
+
+        assert!(timestamps_to_prune(now, &backups).is_empty());
+    }
+
+    #[test]
+    fn thins_daily_tier_to_one_per_day_beyond_recent_window() {
+        // Fixed at midday so both extra backups below land on the same calendar day.
+        let now = DateTime::parse_from_rfc3339("2026-01-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut backups: Vec<BackupInfo> = (0..KEEP_RECENT as i64).map(|i| backup_at(now, i)).collect();
+        // Two extra same-day backups a few hours apart, both past the recent window.
+        backups.push(BackupInfo {
+            timestamp: (now - Duration::days(KEEP_DAILY_DAYS) - Duration::hours(1)).to_rfc3339(),
+            source_filename: "ledger.json".to_string(),
+            size: 0,
+            path: String::new(),
+        });
+        backups.push(BackupInfo {
+            timestamp: (now - Duration::days(KEEP_DAILY_DAYS) - Duration::hours(5)).to_rfc3339(),
+            source_filename: "ledger.json".to_string(),
+            size: 0,
+            path: String::new(),
+        });
+
+        let pruned = timestamps_to_prune(now, &backups);
+
+        assert_eq!(pruned.len(), 1, "only one of the same-day backups should be kept");
+    }
+
+    #[test]
+    fn thins_weekly_tier_beyond_daily_window() {
+        // Fixed so that 16 and 17 days earlier (2025-12-30 and 2025-12-29) share ISO week 2026-W01.
+        let now = DateTime::parse_from_rfc3339("2026-01-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        let mut backups: Vec<BackupInfo> = (0..KEEP_RECENT as i64).map(|i| backup_at(now, i)).collect();
+        // Two backups in the same week, both past the daily tier but within the weekly tier.
+        backups.push(backup_at(now, KEEP_DAILY_DAYS + 2));
+        backups.push(backup_at(now, KEEP_DAILY_DAYS + 3));
+
+        let pruned = timestamps_to_prune(now, &backups);
+
+        assert_eq!(pruned.len(), 1, "only one of the same-week backups should be kept");
+    }
+
+    #[test]
+    fn prunes_everything_past_the_weekly_window() {
+        let now = Utc::now();
+        let mut backups: Vec<BackupInfo> = (0..KEEP_RECENT as i64).map(|i| backup_at(now, i)).collect();
+        backups.push(backup_at(now, KEEP_WEEKLY_DAYS + 10));
+
+        let pruned = timestamps_to_prune(now, &backups);
+
+        assert_eq!(pruned.len(), 1);
+    }
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::write_atomic;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AppConfig {
+    ledgers_dir: Option<String>,
+}
+
+/// Directory holding Ironbank's own config file, e.g. `~/.config/Ironbank` on Linux
+fn config_dir() -> Result<PathBuf, String> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| "Could not find config directory".to_string())?
+        .join("Ironbank");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    Ok(dir)
+}
+
+fn config_file_path() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join("config.json"))
+}
+
+fn read_config() -> AppConfig {
+    config_file_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_config(config: &AppConfig) -> Result<(), String> {
+    let path = config_file_path()?;
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    write_atomic(&path, &json)
+}
+
+/// The persisted ledgers directory override, if the user has set one
+pub fn persisted_ledgers_dir() -> Option<PathBuf> {
+    read_config().ledgers_dir.map(PathBuf::from)
+}
+
+/// Persist `path` as the ledgers directory override
+pub fn set_persisted_ledgers_dir(path: &Path) -> Result<(), String> {
+    let mut config = read_config();
+    config.ledgers_dir = Some(path.to_string_lossy().to_string());
+    write_config(&config)
+}
+
+/// Create `path` if it doesn't already exist, without probing writability
+pub fn ensure_dir_exists(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        fs::create_dir_all(path).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Confirm `path` exists and is writable by probing it with a throwaway file
+pub fn validate_writable_dir(path: &Path) -> Result<(), String> {
+    ensure_dir_exists(path)?;
+
+    let probe = path.join(".ironbank-write-test");
+    fs::write(&probe, b"ok").map_err(|e| format!("Directory is not writable: {}", e))?;
+    let _ = fs::remove_file(&probe);
+
+    Ok(())
+}
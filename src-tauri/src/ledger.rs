@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Floating-point postings within this of zero are considered balanced
+const BALANCE_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Deserialize)]
+pub struct Account {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub currency: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Posting {
+    pub account_id: String,
+    pub amount: f64,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Transaction {
+    #[serde(default)]
+    pub id: String,
+    pub date: String,
+    #[serde(default)]
+    pub postings: Vec<Posting>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Ledger {
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+/// A single invariant violation, tied back to the transaction/line it came from
+#[derive(Debug, Serialize)]
+pub struct ValidationError {
+    pub transaction_id: Option<String>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub errors: Vec<ValidationError>,
+}
+
+/// Parse `content` as a ledger and check its core accounting invariants
+pub fn validate_content(content: &str) -> ValidationReport {
+    let ledger: Ledger = match serde_json::from_str(content) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            return ValidationReport {
+                valid: false,
+                errors: vec![ValidationError {
+                    transaction_id: None,
+                    line: e.line().into(),
+                    message: format!("Failed to parse ledger JSON: {}", e),
+                }],
+            }
+        }
+    };
+
+    let errors = validate(&ledger);
+    ValidationReport {
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+fn validate(ledger: &Ledger) -> Vec<ValidationError> {
+    let accounts: HashMap<&str, &Account> =
+        ledger.accounts.iter().map(|a| (a.id.as_str(), a)).collect();
+    let mut errors = Vec::new();
+
+    for (index, transaction) in ledger.transactions.iter().enumerate() {
+        let transaction_id = if transaction.id.is_empty() {
+            format!("#{}", index)
+        } else {
+            transaction.id.clone()
+        };
+
+        if NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").is_err() {
+            errors.push(ValidationError {
+                transaction_id: Some(transaction_id.clone()),
+                line: None,
+                message: format!("Date '{}' is not a valid YYYY-MM-DD date", transaction.date),
+            });
+        }
+
+        let mut balances: HashMap<String, f64> = HashMap::new();
+
+        for posting in &transaction.postings {
+            let account = match accounts.get(posting.account_id.as_str()) {
+                Some(account) => account,
+                None => {
+                    errors.push(ValidationError {
+                        transaction_id: Some(transaction_id.clone()),
+                        line: None,
+                        message: format!(
+                            "Posting references unknown account '{}'",
+                            posting.account_id
+                        ),
+                    });
+                    continue;
+                }
+            };
+
+            let currency = posting.currency.as_deref().unwrap_or(&account.currency);
+            if currency != account.currency {
+                errors.push(ValidationError {
+                    transaction_id: Some(transaction_id.clone()),
+                    line: None,
+                    message: format!(
+                        "Posting to account '{}' uses currency '{}' but the account is '{}'",
+                        posting.account_id, currency, account.currency
+                    ),
+                });
+            }
+
+            *balances.entry(currency.to_string()).or_insert(0.0) += posting.amount;
+        }
+
+        for (currency, balance) in balances {
+            if balance.abs() > BALANCE_EPSILON {
+                errors.push(ValidationError {
+                    transaction_id: Some(transaction_id.clone()),
+                    line: None,
+                    message: format!(
+                        "Postings in '{}' do not balance to zero ({} {})",
+                        currency, balance, currency
+                    ),
+                });
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_message_containing(report: &ValidationReport, needle: &str) -> bool {
+        report.errors.iter().any(|e| e.message.contains(needle))
+    }
+
+    #[test]
+    fn balanced_postings_are_valid() {
+        let content = r#"{
+            "accounts": [
+                {"id": "cash", "currency": "USD"},
+                {"id": "groceries", "currency": "USD"}
+            ],
+            "transactions": [
+                {
+                    "id": "t1",
+                    "date": "2026-01-15",
+                    "postings": [
+                        {"account_id": "cash", "amount": -42.50},
+                        {"account_id": "groceries", "amount": 42.50}
+                    ]
+                }
+            ]
+        }"#;
+
+        let report = validate_content(content);
+
+        assert!(report.valid);
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn unbalanced_postings_are_rejected() {
+        let content = r#"{
+            "accounts": [
+                {"id": "cash", "currency": "USD"},
+                {"id": "groceries", "currency": "USD"}
+            ],
+            "transactions": [
+                {
+                    "id": "t1",
+                    "date": "2026-01-15",
+                    "postings": [
+                        {"account_id": "cash", "amount": -42.50},
+                        {"account_id": "groceries", "amount": 10.00}
+                    ]
+                }
+            ]
+        }"#;
+
+        let report = validate_content(content);
+
+        assert!(!report.valid);
+        assert!(has_message_containing(&report, "do not balance to zero"));
+    }
+
+    #[test]
+    fn unknown_account_is_rejected() {
+        let content = r#"{
+            "accounts": [
+                {"id": "cash", "currency": "USD"}
+            ],
+            "transactions": [
+                {
+                    "id": "t1",
+                    "date": "2026-01-15",
+                    "postings": [
+                        {"account_id": "cash", "amount": -10.00},
+                        {"account_id": "ghost", "amount": 10.00}
+                    ]
+                }
+            ]
+        }"#;
+
+        let report = validate_content(content);
+
+        assert!(!report.valid);
+        assert!(has_message_containing(&report, "unknown account"));
+    }
+
+    #[test]
+    fn unparseable_date_is_rejected() {
+        let content = r#"{
+            "accounts": [
+                {"id": "cash", "currency": "USD"}
+            ],
+            "transactions": [
+                {
+                    "id": "t1",
+                    "date": "not-a-date",
+                    "postings": []
+                }
+            ]
+        }"#;
+
+        let report = validate_content(content);
+
+        assert!(!report.valid);
+        assert!(has_message_containing(&report, "not a valid YYYY-MM-DD date"));
+    }
+
+    #[test]
+    fn currency_mismatch_is_rejected() {
+        let content = r#"{
+            "accounts": [
+                {"id": "cash", "currency": "USD"},
+                {"id": "savings", "currency": "EUR"}
+            ],
+            "transactions": [
+                {
+                    "id": "t1",
+                    "date": "2026-01-15",
+                    "postings": [
+                        {"account_id": "cash", "amount": -10.00},
+                        {"account_id": "savings", "amount": 10.00, "currency": "USD"}
+                    ]
+                }
+            ]
+        }"#;
+
+        let report = validate_content(content);
+
+        assert!(!report.valid);
+        assert!(has_message_containing(&report, "but the account is 'EUR'"));
+    }
+
+    #[test]
+    fn invalid_json_is_reported_as_a_single_error() {
+        let report = validate_content("{ not json");
+
+        assert!(!report.valid);
+        assert_eq!(report.errors.len(), 1);
+        assert!(has_message_containing(&report, "Failed to parse ledger JSON"));
+    }
+}
@@ -1,8 +1,16 @@
-use std::fs;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tauri::Manager;
 
+mod backup;
+mod config;
+mod ledger;
+use backup::BackupInfo;
+use ledger::ValidationReport;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LedgerInfo {
     pub name: String,
@@ -12,18 +20,49 @@ pub struct LedgerInfo {
     pub size: u64,
 }
 
-/// Get the ledgers directory path
+/// Checksum sidecar metadata for a saved ledger
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LedgerMeta {
+    pub sha256: String,
+    pub revision: u64,
+}
+
+/// Outcome of `verify_ledger`.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyStatus {
+    Ok,
+    Mismatch,
+    MissingSidecar,
+    Unparseable,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub status: VerifyStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecoverReport {
+    pub action: String,
+    pub detail: String,
+}
+
+/// Resolve the ledgers directory: env var, then persisted setting, then the default
 fn get_ledgers_dir() -> Result<PathBuf, String> {
-    let documents = dirs::document_dir()
-        .ok_or_else(|| "Could not find Documents directory".to_string())?;
-    let ledgers_dir = documents.join("Ironbank").join("ledgers");
-    
-    // Create directory if it doesn't exist
-    if !ledgers_dir.exists() {
-        fs::create_dir_all(&ledgers_dir)
-            .map_err(|e| format!("Failed to create ledgers directory: {}", e))?;
-    }
-    
+    let ledgers_dir = if let Ok(env_dir) = std::env::var("IRONBANK_LEDGER_DIR") {
+        PathBuf::from(env_dir)
+    } else if let Some(persisted_dir) = config::persisted_ledgers_dir() {
+        persisted_dir
+    } else {
+        let documents = dirs::document_dir()
+            .ok_or_else(|| "Could not find Documents directory".to_string())?;
+        documents.join("Ironbank").join("ledgers")
+    };
+
+    config::ensure_dir_exists(&ledgers_dir)?;
+
     Ok(ledgers_dir)
 }
 
@@ -41,6 +80,11 @@ fn list_ledgers() -> Result<Vec<LedgerInfo>, String> {
         let path = entry.path();
         
         if path.is_file() {
+            let filename_str = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if filename_str.ends_with(".meta.json") || filename_str.ends_with(".tmp") {
+                continue;
+            }
+
             if let Some(ext) = path.extension() {
                 if ext == "json" {
                     let metadata = fs::metadata(&path)
@@ -94,18 +138,243 @@ fn read_ledger(path: String) -> Result<String, String> {
         .map_err(|e| format!("Failed to read ledger: {}", e))
 }
 
-/// Save a ledger file
+/// Write `content` to `path` via a synced sibling temp file and an atomic rename
+fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let tmp_path = tmp_path_for(path);
+
+    {
+        let mut tmp_file = File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        tmp_file
+            .write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        tmp_file
+            .sync_all()
+            .map_err(|e| format!("Failed to fsync temp file: {}", e))?;
+    }
+
+    fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to commit temp file: {}", e))?;
+
+    Ok(())
+}
+
+/// The write-ahead temp file for `path`, e.g. `ledger.json` -> `ledger.json.tmp`
+fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    path.with_file_name(tmp_name)
+}
+
+/// Roll forward or discard leftover `.tmp` files from an interrupted `write_atomic`
+fn recover_incomplete_writes(dir: &Path) -> Result<(), String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+
+        if path.extension().and_then(|e| e.to_str()) != Some("tmp") {
+            continue;
+        }
+
+        let dest = path.with_extension("");
+        let is_valid = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .is_some();
+
+        if is_valid {
+            let _ = fs::rename(&path, &dest);
+        } else {
+            let _ = fs::remove_file(&path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The sidecar metadata path for a ledger, e.g. `ledger.json` -> `ledger.json.meta.json`
+fn meta_path_for(path: &Path) -> PathBuf {
+    let mut meta_name = path.file_name().unwrap_or_default().to_os_string();
+    meta_name.push(".meta.json");
+    path.with_file_name(meta_name)
+}
+
+fn sha256_hex(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn read_meta(path: &Path) -> Option<LedgerMeta> {
+    let meta_path = meta_path_for(path);
+    let content = fs::read_to_string(meta_path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Write (or bump) the sidecar checksum/revision for `path`'s content
+fn write_meta(path: &Path, content: &str) -> Result<(), String> {
+    let revision = read_meta(path).map(|m| m.revision + 1).unwrap_or(1);
+    let meta = LedgerMeta {
+        sha256: sha256_hex(content),
+        revision,
+    };
+    let meta_json = serde_json::to_string_pretty(&meta)
+        .map_err(|e| format!("Failed to serialize ledger metadata: {}", e))?;
+
+    write_atomic(&meta_path_for(path), &meta_json)
+}
+
+/// Check a ledger's accounting invariants without saving it
 #[tauri::command]
-fn save_ledger(filename: String, content: String) -> Result<String, String> {
+fn validate_ledger(content: String) -> ValidationReport {
+    ledger::validate_content(&content)
+}
+
+/// Save a ledger file, rejecting invalid content when `validate` is true
+#[tauri::command]
+fn save_ledger(filename: String, content: String, validate: Option<bool>) -> Result<String, String> {
+    let validate = validate.unwrap_or(false);
+    let report = ledger::validate_content(&content);
+    if !report.valid {
+        if validate {
+            return Err(serde_json::to_string(&report)
+                .map_err(|e| format!("Failed to serialize validation report: {}", e))?);
+        }
+        log::warn!(
+            "Saving {} with {} ledger validation error(s)",
+            filename,
+            report.errors.len()
+        );
+    }
+
     let ledgers_dir = get_ledgers_dir()?;
     let path = ledgers_dir.join(&filename);
-    
-    fs::write(&path, &content)
-        .map_err(|e| format!("Failed to save ledger: {}", e))?;
-    
+
+    write_atomic(&path, &content)?;
+    write_meta(&path, &content)?;
+
+    // Snapshot off the calling thread so backing up a large ledger never blocks the UI.
+    let snapshot_filename = filename.clone();
+    let snapshot_content = content.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = backup::snapshot(&snapshot_filename, &snapshot_content) {
+            log::warn!("Failed to snapshot backup for {}: {}", snapshot_filename, e);
+        }
+    });
+
     Ok(path.to_string_lossy().to_string())
 }
 
+/// List backup snapshots for a ledger, newest first
+#[tauri::command]
+fn list_backups(filename: String) -> Result<Vec<BackupInfo>, String> {
+    backup::list_backups(&filename)
+}
+
+/// Restore a ledger from one of its backup snapshots
+#[tauri::command]
+fn restore_backup(filename: String, backup_path: String) -> Result<(), String> {
+    let ledgers_dir = get_ledgers_dir()?;
+    let ledger_path = ledgers_dir.join(&filename);
+    backup::restore(&ledger_path, &PathBuf::from(backup_path))
+}
+
+/// Permanently delete a single backup snapshot
+#[tauri::command]
+fn delete_backup(filename: String, backup_path: String) -> Result<(), String> {
+    backup::delete(&PathBuf::from(backup_path), &filename)
+}
+
+/// Re-hash a ledger against its sidecar checksum and report whether it's intact
+#[tauri::command]
+fn verify_ledger(path: String) -> Result<VerifyReport, String> {
+    let path = PathBuf::from(path);
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            return Ok(VerifyReport {
+                status: VerifyStatus::Unparseable,
+                detail: format!("Failed to read ledger: {}", e),
+            })
+        }
+    };
+
+    if serde_json::from_str::<serde_json::Value>(&content).is_err() {
+        return Ok(VerifyReport {
+            status: VerifyStatus::Unparseable,
+            detail: "Ledger content is not valid JSON".to_string(),
+        });
+    }
+
+    let meta = match read_meta(&path) {
+        Some(meta) => meta,
+        None => {
+            return Ok(VerifyReport {
+                status: VerifyStatus::MissingSidecar,
+                detail: "No checksum sidecar found for this ledger".to_string(),
+            })
+        }
+    };
+
+    if sha256_hex(&content) == meta.sha256 {
+        Ok(VerifyReport {
+            status: VerifyStatus::Ok,
+            detail: format!("Checksum matches at revision {}", meta.revision),
+        })
+    } else {
+        Ok(VerifyReport {
+            status: VerifyStatus::Mismatch,
+            detail: format!(
+                "Content hash does not match sidecar revision {}",
+                meta.revision
+            ),
+        })
+    }
+}
+
+/// Recover a corrupted ledger: roll forward a write-ahead temp file, or restore the latest backup
+#[tauri::command]
+fn recover_ledger(path: String) -> Result<RecoverReport, String> {
+    let path = PathBuf::from(path);
+    let tmp_path = tmp_path_for(&path);
+
+    if let Ok(tmp_content) = fs::read_to_string(&tmp_path) {
+        if serde_json::from_str::<serde_json::Value>(&tmp_content).is_ok() {
+            fs::rename(&tmp_path, &path)
+                .map_err(|e| format!("Failed to roll forward temp file: {}", e))?;
+            write_meta(&path, &tmp_content)?;
+            return Ok(RecoverReport {
+                action: "rolled_forward_tmp".to_string(),
+                detail: "Restored ledger from an interrupted write's temp file".to_string(),
+            });
+        }
+    }
+
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "Invalid ledger path".to_string())?;
+
+    if let Some(latest) = backup::list_backups(filename)?.first() {
+        backup::restore(&path, &PathBuf::from(&latest.path))?;
+        return Ok(RecoverReport {
+            action: "restored_from_backup".to_string(),
+            detail: format!("Restored ledger from backup snapshot {}", latest.timestamp),
+        });
+    }
+
+    Ok(RecoverReport {
+        action: "none".to_string(),
+        detail: "No recoverable write-ahead temp file or backup snapshot found".to_string(),
+    })
+}
+
 /// Delete a ledger file
 #[tauri::command]
 fn delete_ledger(path: String) -> Result<(), String> {
@@ -113,38 +382,46 @@ fn delete_ledger(path: String) -> Result<(), String> {
         .map_err(|e| format!("Failed to delete ledger: {}", e))
 }
 
+/// Copy `src` over `dest` crash-safely, keeping the sidecar checksum in sync
+fn copy_atomic(src: &Path, dest: &Path) -> Result<(), String> {
+    let content = fs::read_to_string(src)
+        .map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+    write_atomic(dest, &content)?;
+    write_meta(dest, &content)
+}
+
 /// Reset the tutorial ledger by copying fresh from bundled resources
 #[tauri::command]
 fn reset_tutorial_ledger(app_handle: tauri::AppHandle) -> Result<(), String> {
     let ledgers_dir = get_ledgers_dir()?;
     let tutorial_dest = ledgers_dir.join("tutorial.ledger.json");
-    
+
     // Try to find and copy the bundled tutorial ledger
     if let Ok(resource_path) = app_handle.path().resource_dir() {
         let tutorial_src = resource_path.join("resources").join("tutorial.ledger.json");
         if tutorial_src.exists() {
-            fs::copy(&tutorial_src, &tutorial_dest)
+            copy_atomic(&tutorial_src, &tutorial_dest)
                 .map_err(|e| format!("Failed to reset tutorial ledger: {}", e))?;
             return Ok(());
         }
     }
-    
+
     // For dev mode, try the local resources directory
     #[cfg(debug_assertions)]
     {
         let dev_path = std::env::current_dir()
             .map(|p| p.join("resources").join("tutorial.ledger.json"))
             .ok();
-        
+
         if let Some(dev_src) = dev_path {
             if dev_src.exists() {
-                fs::copy(&dev_src, &tutorial_dest)
+                copy_atomic(&dev_src, &tutorial_dest)
                     .map_err(|e| format!("Failed to reset tutorial ledger: {}", e))?;
                 return Ok(());
             }
         }
     }
-    
+
     Err("Tutorial source file not found".to_string())
 }
 
@@ -155,6 +432,14 @@ fn get_ledgers_directory() -> Result<String, String> {
     Ok(ledgers_dir.to_string_lossy().to_string())
 }
 
+/// Persist a custom ledgers directory after validating it exists and is writable
+#[tauri::command]
+fn set_ledgers_directory(path: String) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    config::validate_writable_dir(&path)?;
+    config::set_persisted_ledgers_dir(&path)
+}
+
 /// Open the ledgers directory in file explorer
 #[tauri::command]
 fn open_ledgers_directory() -> Result<(), String> {
@@ -225,6 +510,10 @@ pub fn run() {
         .setup(|app| {
             // Copy tutorial ledger to ledgers directory on first run
             if let Ok(ledgers_dir) = get_ledgers_dir() {
+                // Roll forward or discard any .tmp files left behind by a write
+                // that was interrupted before its rename could commit.
+                let _ = recover_incomplete_writes(&ledgers_dir);
+
                 let tutorial_dest = ledgers_dir.join("tutorial.ledger.json");
                 
                 // Only copy if tutorial doesn't exist yet
@@ -233,20 +522,20 @@ pub fn run() {
                     if let Ok(resource_path) = app.path().resource_dir() {
                         let tutorial_src = resource_path.join("resources").join("tutorial.ledger.json");
                         if tutorial_src.exists() {
-                            let _ = fs::copy(&tutorial_src, &tutorial_dest);
+                            let _ = copy_atomic(&tutorial_src, &tutorial_dest);
                         }
                     }
-                    
+
                     // For dev mode, try the local resources directory
                     #[cfg(debug_assertions)]
                     {
                         let dev_path = std::env::current_dir()
                             .map(|p| p.join("resources").join("tutorial.ledger.json"))
                             .ok();
-                        
+
                         if let Some(dev_src) = dev_path {
                             if dev_src.exists() && !tutorial_dest.exists() {
-                                let _ = fs::copy(&dev_src, &tutorial_dest);
+                                let _ = copy_atomic(&dev_src, &tutorial_dest);
                             }
                         }
                     }
@@ -266,9 +555,16 @@ pub fn run() {
             list_ledgers,
             read_ledger,
             save_ledger,
+            validate_ledger,
+            verify_ledger,
+            recover_ledger,
+            list_backups,
+            restore_backup,
+            delete_backup,
             delete_ledger,
             reset_tutorial_ledger,
             get_ledgers_directory,
+            set_ledgers_directory,
             open_ledgers_directory,
             get_tutorial_data,
         ])